@@ -1,19 +1,123 @@
 use anyhow::{anyhow, Context, Result};
 use clap::Parser;
 use log::info;
+use serde::Deserialize;
 use std::{
-    fs,
+    collections::BTreeMap,
+    env, fs,
     path::{Path, PathBuf},
     process::{Command, ExitStatus},
 };
-use tempfile::tempdir;
+use tempfile::{tempdir, TempDir};
 //TODO: add logging
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None, trailing_var_arg=true)]
 struct Args {
-    #[clap(short, long, value_parser, name = "crate")]
-    install_crate: String,
+    #[clap(
+        short,
+        long,
+        value_parser,
+        name = "crate",
+        required_unless_present = "path",
+        multiple_values = true
+    )]
+    install_crate: Vec<String>,
+    #[clap(
+        long,
+        value_parser,
+        help = "Version requirement to install, as with `cargo install --version`"
+    )]
+    version: Option<String>,
+    #[clap(
+        long,
+        value_parser,
+        conflicts_with = "path",
+        help = "Git URL to install the crate from instead of a registry"
+    )]
+    git: Option<String>,
+    #[clap(
+        long,
+        value_parser,
+        requires = "git",
+        conflicts_with_all = &["tag", "rev"],
+        help = "Branch to use when installing from --git"
+    )]
+    branch: Option<String>,
+    #[clap(
+        long,
+        value_parser,
+        requires = "git",
+        conflicts_with_all = &["branch", "rev"],
+        help = "Tag to use when installing from --git"
+    )]
+    tag: Option<String>,
+    #[clap(
+        long,
+        value_parser,
+        requires = "git",
+        conflicts_with_all = &["branch", "tag"],
+        help = "Specific commit to use when installing from --git"
+    )]
+    rev: Option<String>,
+    #[clap(
+        long,
+        value_parser,
+        conflicts_with_all = &["git", "version", "index", "registry"],
+        help = "Local path to a crate to install instead of a registry"
+    )]
+    path: Option<PathBuf>,
+    #[clap(
+        long,
+        value_parser,
+        help = "Registry index URL to install from"
+    )]
+    index: Option<String>,
+    #[clap(
+        long,
+        value_parser,
+        help = "Name of the alternate registry to install from"
+    )]
+    registry: Option<String>,
+    #[clap(
+        long,
+        value_parser,
+        help = "Name of the installed binary to run when the crate installs several"
+    )]
+    bin: Option<String>,
+    #[clap(
+        long,
+        action,
+        help = "Install into a throwaway directory instead of the persistent cache"
+    )]
+    no_cache: bool,
+    #[clap(
+        long,
+        action,
+        conflicts_with = "no_cache",
+        help = "Reinstall even when a cached build already exists"
+    )]
+    refresh: bool,
+    #[clap(
+        long,
+        value_parser,
+        multiple_values = true,
+        help = "Space or comma separated list of features to activate"
+    )]
+    features: Vec<String>,
+    #[clap(long, action, help = "Activate all available features")]
+    all_features: bool,
+    #[clap(long, action, help = "Do not activate the `default` feature")]
+    no_default_features: bool,
+    #[clap(
+        long,
+        value_parser,
+        conflicts_with = "debug",
+        help = "Install with the given profile"
+    )]
+    profile: Option<String>,
+    #[clap(long, action, help = "Build in debug mode instead of release")]
+    debug: bool,
     #[clap(
         long,
         multiple_values = true,
@@ -24,35 +128,216 @@ struct Args {
     sub_args: Vec<String>,
 }
 
-fn create_crate_install_command(install_crate: &str, path: &Path) -> Command {
+fn create_crate_install_command(args: &Args, spec: &str, path: &Path) -> Command {
     let mut cmd = Command::new("cargo");
-    cmd.arg("install")
-        .arg(install_crate)
-        .arg("--root")
-        .arg(path);
+    cmd.arg("install");
+
+    // A `--path` install passes no positional spec to cargo (a crate name given
+    // alongside `--path` is not forwarded), and clap rejects the registry
+    // selectors with `--path`, so only a registry or git source carries the
+    // spec, version and registry flags.
+    if let Some(local) = &args.path {
+        cmd.arg("--path").arg(local);
+    } else {
+        if let Some(git) = &args.git {
+            cmd.arg("--git").arg(git);
+            if let Some(branch) = &args.branch {
+                cmd.arg("--branch").arg(branch);
+            }
+            if let Some(tag) = &args.tag {
+                cmd.arg("--tag").arg(tag);
+            }
+            if let Some(rev) = &args.rev {
+                cmd.arg("--rev").arg(rev);
+            }
+        }
+        cmd.arg(spec);
+
+        if let Some(version) = &args.version {
+            cmd.arg("--version").arg(version);
+        }
+        if let Some(index) = &args.index {
+            cmd.arg("--index").arg(index);
+        }
+        if let Some(registry) = &args.registry {
+            cmd.arg("--registry").arg(registry);
+        }
+    }
+
+    for features in &args.features {
+        cmd.arg("--features").arg(features);
+    }
+    if args.all_features {
+        cmd.arg("--all-features");
+    }
+    if args.no_default_features {
+        cmd.arg("--no-default-features");
+    }
+    if let Some(profile) = &args.profile {
+        cmd.arg("--profile").arg(profile);
+    }
+    if args.debug {
+        cmd.arg("--debug");
+    }
+
+    cmd.arg("--root").arg(path);
     cmd
 }
 
-fn find_first_executable(crate_name: &str, search_dir: &Path) -> Result<PathBuf> {
-    info!(
-        "Searching for {}in: {}",
-        crate_name,
-        search_dir.to_string_lossy()
-    );
-    for path in fs::read_dir(search_dir)? {
-        let path = path.unwrap();
-        let file_name = path
-            .path()
-            .file_stem()
-            .unwrap()
-            .to_string_lossy()
-            .to_string();
-
-        if file_name == crate_name {
-            return Ok(path.path());
+/// `cargo install` records every install it performs into `.crates2.json` at the
+/// root, including the real `bin` target names of each package. We read that
+/// rather than guessing the executable name from the crate name.
+///
+/// This file is cargo's internal bookkeeping, not a documented interface covered
+/// by its stability guarantees, so a future cargo release could change its shape.
+/// We prefer it over `cargo metadata` (which needs the crate's source, not just
+/// its installed artifacts) and over parsing the free-form text of
+/// `cargo install --list`: it is already written into our own `--root`, is real
+/// JSON, and names the exact binaries installed. If the format drifts, the serde
+/// parse below fails loudly rather than running the wrong executable.
+#[derive(Deserialize)]
+struct CratesListing {
+    installs: BTreeMap<String, InstallEntry>,
+}
+
+#[derive(Deserialize)]
+struct InstallEntry {
+    bins: Vec<String>,
+}
+
+/// Enumerate the binaries installed under `root` as reported by cargo.
+fn installed_binaries(root: &Path) -> Result<Vec<String>> {
+    let listing_path = root.join(".crates2.json");
+    info!("Reading installed binaries from: {}", listing_path.display());
+    let contents = fs::read_to_string(&listing_path)
+        .with_context(|| format!("Failed to read {}", listing_path.display()))?;
+    let listing: CratesListing =
+        serde_json::from_str(&contents).context("Failed to parse cargo install metadata")?;
+    let bins = listing
+        .installs
+        .into_values()
+        .flat_map(|entry| entry.bins)
+        .collect();
+    Ok(bins)
+}
+
+/// Pick the binary to run from those installed. An explicit `--bin` request wins
+/// and errors with the available names if it doesn't match. Otherwise a single
+/// binary is used directly, the one matching the crate name is preferred, and if
+/// neither applies the caller is shown the available choices.
+fn select_binary(crate_name: &str, requested: Option<&str>, bins: &[String]) -> Result<String> {
+    if bins.is_empty() {
+        return Err(anyhow!("No executables were installed"));
+    }
+
+    if let Some(requested) = requested {
+        return bins
+            .iter()
+            .find(|bin| *bin == requested)
+            .cloned()
+            .ok_or_else(|| {
+                anyhow!(
+                    "No binary named `{}` was installed, available binaries: {}",
+                    requested,
+                    bins.join(", ")
+                )
+            });
+    }
+
+    if let [only] = bins {
+        return Ok(only.clone());
+    }
+
+    if let Some(matched) = bins.iter().find(|bin| *bin == crate_name) {
+        return Ok(matched.clone());
+    }
+
+    Err(anyhow!(
+        "Several executables were installed, pass --bin to choose one of: {}",
+        bins.join(", ")
+    ))
+}
+
+/// Where cached installs live. Honours `$CARGO_TRY_CACHE`, falling back to the
+/// platform cache directory (`$XDG_CACHE_HOME` or `~/.cache`).
+fn cache_root() -> Result<PathBuf> {
+    if let Some(dir) = env::var_os("CARGO_TRY_CACHE") {
+        return Ok(PathBuf::from(dir));
+    }
+    let base = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .ok_or_else(|| {
+            anyhow!("Could not determine a cache directory, set CARGO_TRY_CACHE to choose one")
+        })?;
+    Ok(base.join("cargo-try"))
+}
+
+/// Whether a source is safe to cache. A cache hit skips `cargo install` entirely,
+/// so only immutable sources qualify: a registry install pinned to an explicit
+/// version, or a git source pinned to a specific commit. Unpinned versions, git
+/// branches/tags and local paths can all change underneath us, so they always
+/// install fresh.
+fn is_cacheable(args: &Args, inline_version: Option<&str>) -> bool {
+    if args.path.is_some() {
+        return false;
+    }
+    if args.git.is_some() {
+        return args.rev.is_some();
+    }
+    args.version.is_some() || inline_version.is_some()
+}
+
+/// Cache entries are keyed on the crate, its version and the source it came from,
+/// so trying a different version or commit doesn't reuse the wrong build. Only
+/// immutable sources reach here (see [`is_cacheable`]).
+fn cache_key(crate_name: &str, version: &str, args: &Args) -> String {
+    let source = if let Some(path) = &args.path {
+        format!("path:{}", path.display())
+    } else if let Some(git) = &args.git {
+        let mut source = format!("git:{}", git);
+        if let Some(rev) = args.branch.as_deref().or(args.tag.as_deref()).or(args.rev.as_deref()) {
+            source.push('@');
+            source.push_str(rev);
         }
+        source
+    } else if let Some(registry) = &args.registry {
+        format!("registry:{}", registry)
+    } else if let Some(index) = &args.index {
+        format!("index:{}", index)
+    } else {
+        "crates-io".to_string()
+    };
+    // The enabled features and profile change the produced binary, so fold them
+    // into the key too.
+    let mut build = String::new();
+    if !args.features.is_empty() {
+        build.push_str("-feat:");
+        build.push_str(&args.features.join(","));
+    }
+    if args.all_features {
+        build.push_str("-allfeat");
+    }
+    if args.no_default_features {
+        build.push_str("-nodefault");
+    }
+    if let Some(profile) = &args.profile {
+        build.push_str("-profile:");
+        build.push_str(profile);
+    }
+    if args.debug {
+        build.push_str("-debug");
     }
-    Err(anyhow!("Could not find crate with name {}", crate_name))
+    let raw = format!("{crate_name}-{version}-{source}{build}");
+    raw.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
 }
 
 fn valid_crate_name(name: &str) -> bool {
@@ -66,52 +351,93 @@ fn valid_crate_name(name: &str) -> bool {
         && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
 }
 
-fn main_body(args: &Args) -> Result<ExitStatus> {
-    if !valid_crate_name(&args.install_crate) {
+/// The root a crate is installed into. A throwaway [`TempDir`] when caching is
+/// disabled, or a persistent directory under the cache otherwise.
+enum InstallRoot {
+    Temp(TempDir),
+    Cached(PathBuf),
+}
+
+impl InstallRoot {
+    fn path(&self) -> &Path {
+        match self {
+            InstallRoot::Temp(dir) => dir.path(),
+            InstallRoot::Cached(path) => path,
+        }
+    }
+}
+
+/// Install a single crate `spec` and run its executable, passing `sub_args` to it.
+fn try_crate(args: &Args, spec: &str, sub_args: &[String]) -> Result<ExitStatus> {
+    // `cargo install` accepts a `name@version` spec; validate only the name part.
+    let (crate_name, inline_version) = match spec.split_once('@') {
+        Some((name, version)) => (name, Some(version)),
+        None => (spec, None),
+    };
+    // A `--path` install has no crate spec to validate.
+    if !spec.is_empty() && !valid_crate_name(crate_name) {
         println!("Invalid crate name provided");
         return Err(anyhow!("Invalid crate name provided"));
     }
 
-    let dir = tempdir()?;
-
-    info!("Installing to: {}", dir.path().display());
+    // Decide where to install and whether an install is even needed. Mutable
+    // sources are never cached (see `is_cacheable`), since a cache hit skips the
+    // install entirely and would otherwise run a stale binary.
+    let version = args.version.as_deref().or(inline_version).unwrap_or("latest");
+    let (root, cache_hit) = if args.no_cache || !is_cacheable(args, inline_version) {
+        if !args.no_cache {
+            info!("Source is mutable (unpinned version, git branch/tag, or local path), installing fresh without caching");
+        }
+        (InstallRoot::Temp(tempdir()?), false)
+    } else {
+        let dir = cache_root()?.join(cache_key(crate_name, version, args));
+        if args.refresh && dir.exists() {
+            info!("Refreshing cached install at: {}", dir.display());
+            fs::remove_dir_all(&dir)?;
+        }
+        let hit = dir.join(".crates2.json").is_file();
+        if !hit {
+            fs::create_dir_all(&dir)?;
+        }
+        (InstallRoot::Cached(dir), hit)
+    };
 
-    let output = create_crate_install_command(&args.install_crate, dir.path())
-        .status()
-        .context("Failed to execute cargo install")?;
+    if cache_hit {
+        info!("Using cached install at: {}", root.path().display());
+    } else {
+        info!("Installing to: {}", root.path().display());
+        let output = create_crate_install_command(args, spec, root.path())
+            .status()
+            .context("Failed to execute cargo install")?;
 
-    if !output.success() {
-        return Err(anyhow!(
-            "Failed to install, returned with status code: {}",
-            output.code().unwrap_or_default()
-        ));
+        if !output.success() {
+            return Err(anyhow!(
+                "Failed to install, returned with status code: {}",
+                output.code().unwrap_or_default()
+            ));
+        }
     }
 
-    let exec_file = find_first_executable(&args.install_crate, dir.path().join("bin").as_path())
-        .context("Failed to find same-named executable after install")?;
-    info!(
-        "Found executable matching install name: {}",
-        &args.install_crate
-    );
-    let cwd = dir.path().join("cwd");
+    let bins = installed_binaries(root.path())?;
+    let bin_name = select_binary(crate_name, args.bin.as_deref(), &bins)?;
+    let exec_file = root.path().join("bin").join(&bin_name);
+    info!("Found executable to run: {}", bin_name);
 
-    info!("Creating CWD dir at: {}", cwd.display());
-    fs::create_dir(&cwd)?;
+    // Always run in a fresh working directory so cached installs don't accumulate
+    // state between runs.
+    let cwd = tempdir()?;
+    info!("Created CWD dir at: {}", cwd.path().display());
 
-    let mut inner_cmd = std::process::Command::new(exec_file);
-    inner_cmd.current_dir(&cwd);
+    let mut inner_cmd = Command::new(exec_file);
+    inner_cmd.current_dir(cwd.path());
 
-    if args.sub_args.is_empty() {
-        info!("Running {}", &args.install_crate);
+    if sub_args.is_empty() {
+        info!("Running {}", spec);
     } else {
-        info!(
-            "Running {} with args {}",
-            &args.install_crate,
-            &args.sub_args.join(" ")
-        );
+        info!("Running {} with args {}", spec, sub_args.join(" "));
     }
 
-    let inner_status = inner_cmd.args(&args.sub_args).status()?;
+    let inner_status = inner_cmd.args(sub_args).status()?;
 
     info!(
         "Exited with status code: {}",
@@ -120,10 +446,66 @@ fn main_body(args: &Args) -> Result<ExitStatus> {
     Ok(inner_status)
 }
 
+fn main_body(args: &Args) -> Result<i32> {
+    // Sub-args are forwarded to the executable, which is only meaningful when a
+    // single crate is being tried.
+    if !args.sub_args.is_empty() && args.install_crate.len() > 1 {
+        return Err(anyhow!(
+            "--sub-args can only be used when trying a single crate"
+        ));
+    }
+
+    // With `--path` there is no positional spec; an empty spec drives a single
+    // path install.
+    let specs: Vec<&str> = if args.install_crate.is_empty() {
+        vec![""]
+    } else {
+        args.install_crate.iter().map(String::as_str).collect()
+    };
+
+    // Run every crate to completion, collecting each outcome rather than aborting
+    // the whole batch on the first failure.
+    let results: Vec<(String, Result<ExitStatus>)> = specs
+        .iter()
+        .map(|spec| {
+            let label = if spec.is_empty() {
+                "(local path)".to_string()
+            } else {
+                spec.to_string()
+            };
+            (label, try_crate(args, spec, &args.sub_args))
+        })
+        .collect();
+
+    // Report how each crate fared and surface the first failure in the exit code.
+    println!("Summary:");
+    let mut exit_code = 0;
+    for (label, outcome) in &results {
+        match outcome {
+            Ok(status) => {
+                let code = status.code().unwrap_or_default();
+                println!("  {}: exited with {}", label, code);
+                if code != 0 && exit_code == 0 {
+                    exit_code = code;
+                }
+            }
+            Err(err) => {
+                println!("  {}: failed: {:#}", label, err);
+                if exit_code == 0 {
+                    exit_code = 1;
+                }
+            }
+        }
+    }
+
+    Ok(exit_code)
+}
+
 fn main() {
     env_logger::init();
     let args = Args::parse();
-    main_body(&args).unwrap();
+    let exit_code = main_body(&args).unwrap();
+    std::process::exit(exit_code);
 }
 
 #[cfg(test)]
@@ -136,10 +518,26 @@ mod tests {
     fn check_install() -> Result<()> {
         init();
         let args = Args {
-            install_crate: "status-return".into(),
+            install_crate: vec!["status-return".into()],
+            version: None,
+            git: None,
+            branch: None,
+            tag: None,
+            rev: None,
+            path: None,
+            index: None,
+            registry: None,
+            bin: None,
+            no_cache: true,
+            refresh: false,
+            features: vec![],
+            all_features: false,
+            no_default_features: false,
+            profile: None,
+            debug: false,
             sub_args: vec![],
         };
-        assert!(main_body(&args)?.code().unwrap() == 42);
+        assert!(main_body(&args)? == 42);
         Ok(())
     }
 
@@ -147,10 +545,252 @@ mod tests {
     fn check_install_with_sub_args() -> Result<()> {
         init();
         let args = Args {
-            install_crate: "status-return".into(),
+            install_crate: vec!["status-return".into()],
+            version: None,
+            git: None,
+            branch: None,
+            tag: None,
+            rev: None,
+            path: None,
+            index: None,
+            registry: None,
+            bin: None,
+            no_cache: true,
+            refresh: false,
+            features: vec![],
+            all_features: false,
+            no_default_features: false,
+            profile: None,
+            debug: false,
             sub_args: vec!["99".into()],
         };
-        assert!(main_body(&args)?.code().unwrap() == 99);
+        assert!(main_body(&args)? == 99);
         Ok(())
     }
+
+    /// A minimal `Args` for exercising the pure helpers, with caching disabled.
+    fn bare_args() -> Args {
+        Args {
+            install_crate: vec!["foo".into()],
+            version: None,
+            git: None,
+            branch: None,
+            tag: None,
+            rev: None,
+            path: None,
+            index: None,
+            registry: None,
+            bin: None,
+            no_cache: true,
+            refresh: false,
+            features: vec![],
+            all_features: false,
+            no_default_features: false,
+            profile: None,
+            debug: false,
+            sub_args: vec![],
+        }
+    }
+
+    fn bins(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    /// The arguments of a built `Command` as strings, for asserting translation.
+    fn argv(cmd: &Command) -> Vec<String> {
+        cmd.get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn install_command_translates_git_source() {
+        let mut args = bare_args();
+        args.git = Some("https://example.com/foo".into());
+        args.branch = Some("dev".into());
+        let cmd = create_crate_install_command(&args, "foo", Path::new("/tmp/root"));
+        let argv = argv(&cmd);
+        assert_eq!(
+            argv,
+            vec![
+                "install",
+                "--git",
+                "https://example.com/foo",
+                "--branch",
+                "dev",
+                "foo",
+                "--root",
+                "/tmp/root",
+            ]
+        );
+    }
+
+    #[test]
+    fn install_command_path_drops_spec_and_registry_flags() {
+        let mut args = bare_args();
+        args.install_crate = vec![];
+        args.path = Some(PathBuf::from("./local"));
+        let cmd = create_crate_install_command(&args, "", Path::new("/tmp/root"));
+        let argv = argv(&cmd);
+        assert_eq!(
+            argv,
+            vec!["install", "--path", "./local", "--root", "/tmp/root"]
+        );
+    }
+
+    #[test]
+    fn install_command_forwards_version_and_registry() {
+        let mut args = bare_args();
+        args.version = Some("1.2.3".into());
+        args.registry = Some("my-registry".into());
+        let cmd = create_crate_install_command(&args, "foo", Path::new("/tmp/root"));
+        let argv = argv(&cmd);
+        assert_eq!(
+            argv,
+            vec![
+                "install",
+                "foo",
+                "--version",
+                "1.2.3",
+                "--registry",
+                "my-registry",
+                "--root",
+                "/tmp/root",
+            ]
+        );
+    }
+
+    #[test]
+    fn select_binary_single_runs_it() {
+        assert_eq!(select_binary("anything", None, &bins(&["rg"])).unwrap(), "rg");
+    }
+
+    #[test]
+    fn select_binary_prefers_crate_name_match() {
+        let installed = bins(&["helper", "tool"]);
+        assert_eq!(select_binary("tool", None, &installed).unwrap(), "tool");
+    }
+
+    #[test]
+    fn select_binary_honours_requested_bin() {
+        let installed = bins(&["helper", "tool"]);
+        assert_eq!(
+            select_binary("tool", Some("helper"), &installed).unwrap(),
+            "helper"
+        );
+    }
+
+    #[test]
+    fn select_binary_errors_on_missing_requested_bin() {
+        let installed = bins(&["helper", "tool"]);
+        assert!(select_binary("tool", Some("nope"), &installed).is_err());
+    }
+
+    #[test]
+    fn select_binary_errors_when_ambiguous() {
+        let installed = bins(&["one", "two"]);
+        assert!(select_binary("neither", None, &installed).is_err());
+    }
+
+    #[test]
+    fn install_command_forwards_feature_and_profile_flags() {
+        let mut args = bare_args();
+        args.features = vec!["a".into(), "b".into()];
+        args.all_features = true;
+        args.no_default_features = true;
+        args.profile = Some("release-lto".into());
+        let cmd = create_crate_install_command(&args, "foo", Path::new("/tmp/root"));
+        let argv = argv(&cmd);
+        assert_eq!(
+            argv,
+            vec![
+                "install",
+                "foo",
+                "--features",
+                "a",
+                "--features",
+                "b",
+                "--all-features",
+                "--no-default-features",
+                "--profile",
+                "release-lto",
+                "--root",
+                "/tmp/root",
+            ]
+        );
+    }
+
+    #[test]
+    fn cache_key_varies_with_version() {
+        let args = bare_args();
+        assert_ne!(
+            cache_key("foo", "1.0.0", &args),
+            cache_key("foo", "2.0.0", &args)
+        );
+    }
+
+    #[test]
+    fn cache_key_varies_with_git_ref() {
+        let mut a = bare_args();
+        a.git = Some("https://example.com/foo".into());
+        a.branch = Some("main".into());
+        let mut b = bare_args();
+        b.git = Some("https://example.com/foo".into());
+        b.branch = Some("dev".into());
+        assert_ne!(
+            cache_key("foo", "latest", &a),
+            cache_key("foo", "latest", &b)
+        );
+    }
+
+    #[test]
+    fn cache_key_varies_with_features_and_profile() {
+        let plain = bare_args();
+        let mut featured = bare_args();
+        featured.features = vec!["extra".into()];
+        let mut debug = bare_args();
+        debug.debug = true;
+        assert_ne!(
+            cache_key("foo", "latest", &plain),
+            cache_key("foo", "latest", &featured)
+        );
+        assert_ne!(
+            cache_key("foo", "latest", &plain),
+            cache_key("foo", "latest", &debug)
+        );
+    }
+
+    #[test]
+    fn unpinned_registry_source_is_not_cacheable() {
+        let args = bare_args();
+        assert!(!is_cacheable(&args, None));
+        assert!(is_cacheable(&args, Some("1.2.3")));
+    }
+
+    #[test]
+    fn pinned_version_is_cacheable() {
+        let mut args = bare_args();
+        args.version = Some("1.2.3".into());
+        assert!(is_cacheable(&args, None));
+    }
+
+    #[test]
+    fn git_branch_is_not_cacheable_but_rev_is() {
+        let mut branch = bare_args();
+        branch.git = Some("https://example.com/foo".into());
+        branch.branch = Some("main".into());
+        assert!(!is_cacheable(&branch, None));
+
+        let mut rev = bare_args();
+        rev.git = Some("https://example.com/foo".into());
+        rev.rev = Some("abc123".into());
+        assert!(is_cacheable(&rev, None));
+    }
+
+    #[test]
+    fn local_path_is_not_cacheable() {
+        let mut args = bare_args();
+        args.path = Some(PathBuf::from("./local"));
+        assert!(!is_cacheable(&args, Some("1.0.0")));
+    }
 }